@@ -1,13 +1,17 @@
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
-use std::ops::BitXor;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, RwLock};
+use std::task::{Context, Poll};
+use std::thread;
 use std::time::Duration;
 
-use gpio_cdev::{Chip, chips, LineDirection, LineHandle, LineInfo, LineRequestFlags};
+use futures::stream::Stream;
+use gpio_cdev::{Chip, chips, EventRequestFlags, EventType, LineDirection, LineEvent, LineHandle, LineInfo, LineRequestFlags, MultiLineHandle};
 use gpio_cdev::errors::Error;
-use log::{debug, error, info};
-use serde::Serialize;
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
 
 #[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Clone)]
 pub struct GpioPath {
@@ -21,9 +25,47 @@ impl GpioPath {
     }
 }
 
+/// Several lines on the same chip, addressed together for a single kernel ioctl (see
+/// `State::read_many`/`write_many`).
+///
+/// `Eq`/`Hash` include `pins` in the exact order given: a `MultiLineHandle`'s `get_values`/
+/// `set_values` are positional, so reusing a cached handle for a reordered request would
+/// silently shuffle which value goes to which line.
+#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Debug, Clone)]
+pub struct GpioMultiPath {
+    chip: String,
+    pins: Vec<u32>,
+}
+
+impl GpioMultiPath {
+    pub fn new(chip: String, pins: Vec<u32>) -> Self {
+        Self { chip, pins }
+    }
+}
+
+/// The desired shape of a `POST /blink` request: toggle the line at a fixed duty cycle,
+/// `repeat` times (0 means "until cancelled").
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct BlinkSchedule {
+    pub on_ms: u16,
+    pub off_ms: u16,
+    #[serde(default)]
+    pub repeat: u32,
+    #[serde(default)]
+    pub config: LineConfig,
+}
+
+#[derive(Serialize)]
+pub struct BlinkTask {
+    pub task_id: String,
+}
+
 #[derive(Debug)]
 pub enum AppError {
     Gpio(gpio_cdev::errors::Error),
+    AlreadySubscribed(GpioPath),
+    MismatchedLineCount { lines: usize, values: usize },
+    UnknownAlias(String),
 }
 
 pub type AppResult<O> = Result<O, AppError>;
@@ -38,21 +80,180 @@ impl Display for AppError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             AppError::Gpio(e) => write!(f, "{}", e),
+            AppError::AlreadySubscribed(path) => {
+                write!(f, "{:?} already has an events subscriber", path)
+            }
+            AppError::MismatchedLineCount { lines, values } => write!(
+                f,
+                "{} lines were given but {} values were given",
+                lines, values
+            ),
+            AppError::UnknownAlias(name) => write!(f, "no pin alias named {:?} is configured", name),
         }
     }
 }
 
 impl std::error::Error for AppError {}
 
+/// Electrical line settings layered on top of the bare direction, translated into extra
+/// `LineRequestFlags` bits.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, rename_all = "snake_case")]
+pub struct LineConfig {
+    pub active_low: bool,
+    pub drive: Drive,
+    pub bias: Bias,
+    /// Accepted but not yet applied: `gpio_cdev`'s `LineRequestFlags` has no debounce bit.
+    pub debounce_us: Option<u32>,
+}
+
+impl LineConfig {
+    fn apply(self, mut flags: LineRequestFlags) -> LineRequestFlags {
+        if self.active_low {
+            flags |= LineRequestFlags::ACTIVE_LOW;
+        }
+        flags |= match self.drive {
+            Drive::PushPull => LineRequestFlags::empty(),
+            Drive::OpenDrain => LineRequestFlags::OPEN_DRAIN,
+            Drive::OpenSource => LineRequestFlags::OPEN_SOURCE,
+        };
+        flags |= match self.bias {
+            Bias::Disabled => LineRequestFlags::empty(),
+            Bias::PullUp => LineRequestFlags::BIAS_PULL_UP,
+            Bias::PullDown => LineRequestFlags::BIAS_PULL_DOWN,
+        };
+        flags
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Drive {
+    PushPull,
+    OpenDrain,
+    OpenSource,
+}
+
+impl Default for Drive {
+    fn default() -> Self {
+        Drive::PushPull
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Bias {
+    Disabled,
+    PullUp,
+    PullDown,
+}
+
+impl Default for Bias {
+    fn default() -> Self {
+        Bias::Disabled
+    }
+}
+
+/// Which edge transitions a `GET /gpio/{chip}/{pin}/events` subscriber wants to be told about.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EdgeFilter {
+    Rising,
+    Falling,
+    Both,
+}
+
+impl Default for EdgeFilter {
+    fn default() -> Self {
+        EdgeFilter::Both
+    }
+}
+
+impl EdgeFilter {
+    fn flags(self) -> EventRequestFlags {
+        match self {
+            EdgeFilter::Rising => EventRequestFlags::RISING_EDGE,
+            EdgeFilter::Falling => EventRequestFlags::FALLING_EDGE,
+            EdgeFilter::Both => EventRequestFlags::BOTH_EDGES,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct GpioEdgeEvent {
+    edge: &'static str,
+    timestamp_ns: u64,
+    value: u8,
+}
+
+impl From<LineEvent> for GpioEdgeEvent {
+    fn from(event: LineEvent) -> Self {
+        let (edge, value) = match event.event_type() {
+            EventType::RisingEdge => ("rising", 1),
+            EventType::FallingEdge => ("falling", 0),
+        };
+        Self { edge, timestamp_ns: event.timestamp(), value }
+    }
+}
+
+/// A live `GET /events` subscription; dropping it frees the pin for a new subscriber.
+pub struct EventSubscription {
+    inner: Pin<Box<dyn Stream<Item=Result<LineEvent, Error>> + Send>>,
+    state: Arc<State>,
+    path: GpioPath,
+}
+
+impl Stream for EventSubscription {
+    type Item = AppResult<GpioEdgeEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx).map(|opt| {
+            opt.map(|res| res.map(GpioEdgeEvent::from).map_err(AppError::from))
+        })
+    }
+}
+
+impl Drop for EventSubscription {
+    fn drop(&mut self) {
+        self.state.events.write().unwrap().remove(&self.path);
+    }
+}
+
 pub struct State {
-    pins: RwLock<HashMap<GpioPath, Arc<LineHandle>>>,
+    pins: RwLock<HashMap<GpioPath, (LineRequestFlags, Arc<LineHandle>)>>,
+    /// Pins with a live `events` subscription, kept separate from `pins` so `do_with_handle`'s
+    /// retry logic never tears one down.
+    events: RwLock<HashMap<GpioPath, ()>>,
+    /// Multi-line handles, keyed by chip+offset set, mirroring `pins` but for `GpioMultiPath`.
+    multi_pins: RwLock<HashMap<GpioMultiPath, (LineRequestFlags, Arc<MultiLineHandle>)>>,
+    /// Cancellation flags for running blink loops, keyed by pin; replacing the entry cancels
+    /// the old loop.
+    blinks: RwLock<HashMap<GpioPath, Arc<AtomicBool>>>,
+    /// Pins we've already warned about an ignored `debounce_us`, so a client polling `read`/
+    /// `write` doesn't flood the log with the same warning on every request.
+    debounce_warned: RwLock<HashMap<GpioPath, ()>>,
 }
 
 impl State {
     pub fn new() -> Self {
-        let active_pins = HashMap::<GpioPath, Arc<LineHandle>>::new();
+        let active_pins = HashMap::<GpioPath, (LineRequestFlags, Arc<LineHandle>)>::new();
         Self {
             pins: RwLock::new(active_pins),
+            events: RwLock::new(HashMap::new()),
+            multi_pins: RwLock::new(HashMap::new()),
+            blinks: RwLock::new(HashMap::new()),
+            debounce_warned: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Warns once per pin that `debounce_us` was requested but is not applied, instead of on
+    /// every `read`/`write` call.
+    fn warn_debounce_once(&self, gpio_path: &GpioPath, config: &LineConfig) {
+        if let Some(debounce_us) = config.debounce_us {
+            if !self.debounce_warned.read().unwrap().contains_key(gpio_path) {
+                warn!("debounce_us={} was requested for {:?} but is not applied: gpio_cdev has no debounce flag", debounce_us, gpio_path);
+                self.debounce_warned.write().unwrap().insert(gpio_path.clone(), ());
+            }
         }
     }
 
@@ -71,24 +272,34 @@ impl State {
         E: Display,
     {
         debug!("Trying to acquire a read lock on pins");
-        {
-            // Read lock
+        let cached_handle = {
+            // Read lock, dropped before `action` runs so a long-running action (e.g. a blink
+            // loop) never holds it and starves writers on other pins.
             let pins = self.pins.read().unwrap();
-            if let Some(handle) = pins.get(&gpio_path) {
-                match action(handle) {
-                    Ok(res) => {
-                        debug!("Action succeeded with pre-existing pin handle");
-                        return Ok(res); // Happy path, no write lock
-                    }
-                    Err(e) => {
-                        debug!(
-                            "Action failed with pre-existing pin handle ({}); freeing it",
-                            e
-                        );
-                    }
+            match pins.get(&gpio_path) {
+                Some((existing_flags, handle)) if *existing_flags == flags => Some(Arc::clone(handle)),
+                Some(_) => {
+                    debug!("Pre-existing pin handle was opened with different flags");
+                    None
+                }
+                None => {
+                    debug!("No pre-existing pin handle");
+                    None
+                }
+            }
+        };
+        if let Some(handle) = cached_handle {
+            match action(&handle) {
+                Ok(res) => {
+                    debug!("Action succeeded with pre-existing pin handle");
+                    return Ok(res); // Happy path, no write lock
+                }
+                Err(e) => {
+                    debug!(
+                        "Action failed with pre-existing pin handle ({}); freeing it",
+                        e
+                    );
                 }
-            } else {
-                debug!("No pre-existing pin handle")
             }
         }
         // slow path, application state is locked
@@ -104,7 +315,7 @@ impl State {
         let handle = line.request(flags, 0, "http-gpio")?;
         let arc_handle = Arc::new(handle);
         debug!("Saving the pin handle for later");
-        pins.insert(gpio_path, Arc::clone(&arc_handle));
+        pins.insert(gpio_path, (flags, Arc::clone(&arc_handle)));
         // Release the lock
         drop(pins);
         debug!("Performing action");
@@ -112,28 +323,239 @@ impl State {
         Ok(result)
     }
 
-    pub fn read(&self, gpio_path: GpioPath) -> AppResult<u8> {
-        self.do_with_handle(gpio_path, LineRequestFlags::INPUT, |line| line.get_value())
+    pub fn read(&self, gpio_path: GpioPath, config: LineConfig) -> AppResult<u8> {
+        self.warn_debounce_once(&gpio_path, &config);
+        let flags = config.apply(LineRequestFlags::INPUT);
+        self.do_with_handle(gpio_path, flags, |line| line.get_value())
     }
 
-    pub fn write(&self, gpio_path: GpioPath, value: u8) -> AppResult<()> {
-        self.do_with_handle(gpio_path, LineRequestFlags::OUTPUT, |line| {
+    pub fn write(&self, gpio_path: GpioPath, value: u8, config: LineConfig) -> AppResult<()> {
+        self.warn_debounce_once(&gpio_path, &config);
+        let flags = config.apply(LineRequestFlags::OUTPUT);
+        self.do_with_handle(gpio_path, flags, |line| {
             line.set_value(value)
         })
     }
 
-    pub fn write_schedule(&self, gpio_path: GpioPath, schedule: Vec<u16>) -> AppResult<u8> {
-        let pin = gpio_path.pin;
-        info!("Will blink {:?} for a total of {} milliseconds", gpio_path, schedule.iter().sum::<u16>());
-        self.do_with_handle(gpio_path, LineRequestFlags::OUTPUT, |line| -> AppResult<u8>{
-            let mut value = 0;
-            for &time in &schedule {
-                debug!("Setting {} to {}", pin, value);
-                line.set_value(value)?;
-                value = value.bitxor(1);
-                std::thread::sleep(Duration::from_millis(time.into()))
+    /// Starts a blink loop on a dedicated thread and returns immediately, cancelling any blink
+    /// already running on this pin.
+    pub fn write_schedule(
+        self: &Arc<Self>,
+        gpio_path: GpioPath,
+        schedule: BlinkSchedule,
+    ) -> AppResult<BlinkTask> {
+        self.warn_debounce_once(&gpio_path, &schedule.config);
+        let flags = schedule.config.apply(LineRequestFlags::OUTPUT);
+        let cancelled = Arc::new(AtomicBool::new(false));
+        // Cancel-and-replace under a single write lock, so two concurrent requests for the same
+        // pin can't both see "nothing to cancel" and end up with two untracked loops running.
+        if let Some(old) = self.blinks.write().unwrap().insert(gpio_path.clone(), Arc::clone(&cancelled)) {
+            old.store(true, Ordering::SeqCst);
+        }
+
+        let task_id = format!("{}:{}", gpio_path.chip, gpio_path.pin);
+        let state = Arc::clone(self);
+        let thread_path = gpio_path.clone();
+        info!("Starting blink loop for {:?}: {:?}", gpio_path, schedule);
+        thread::spawn(move || {
+            let result = state.do_with_handle(thread_path.clone(), flags, |line| -> AppResult<()> {
+                let mut completed = 0u32;
+                loop {
+                    if cancelled.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    line.set_value(1)?;
+                    if Self::interruptible_sleep(Duration::from_millis(schedule.on_ms.into()), &cancelled) {
+                        break;
+                    }
+                    line.set_value(0)?;
+                    if Self::interruptible_sleep(Duration::from_millis(schedule.off_ms.into()), &cancelled) {
+                        break;
+                    }
+                    completed += 1;
+                    if schedule.repeat != 0 && completed >= schedule.repeat {
+                        break;
+                    }
+                }
+                // Leave the line in a defined state (off) whether we stopped or were cancelled —
+                // but only if we weren't superseded by a replacement blink loop in the meantime;
+                // otherwise this stray write races the new loop's toggling (same `ptr_eq` check
+                // as the bookkeeping cleanup below).
+                let superseded = state.blinks.read().unwrap().get(&thread_path)
+                    .map_or(true, |current| !Arc::ptr_eq(current, &cancelled));
+                if !superseded {
+                    line.set_value(0)?;
+                }
+                Ok(())
+            });
+            if let Err(e) = result {
+                error!("Blink loop for {:?} ended with an error: {}", thread_path, e);
+            }
+            let mut blinks = state.blinks.write().unwrap();
+            if blinks.get(&thread_path).map_or(false, |current| Arc::ptr_eq(current, &cancelled)) {
+                blinks.remove(&thread_path);
+            }
+        });
+        Ok(BlinkTask { task_id })
+    }
+
+    /// Sleeps in short ticks so a cancellation is noticed quickly; returns `true` if cancelled
+    /// before the full duration elapsed.
+    fn interruptible_sleep(duration: Duration, cancelled: &AtomicBool) -> bool {
+        const TICK: Duration = Duration::from_millis(20);
+        let mut remaining = duration;
+        while !remaining.is_zero() {
+            if cancelled.load(Ordering::SeqCst) {
+                return true;
+            }
+            let step = remaining.min(TICK);
+            thread::sleep(step);
+            remaining -= step;
+        }
+        cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Stops the blink loop running on this pin, if any. Returns whether one was running.
+    ///
+    /// Only flips the cancellation flag — the `blinks` entry is left in place so the loop's own
+    /// "was I superseded?" check (see `write_schedule`) still finds its own flag and performs the
+    /// off-write, instead of mistaking an explicit cancel for having been replaced.
+    pub fn cancel_blink(&self, gpio_path: &GpioPath) -> bool {
+        match self.blinks.read().unwrap().get(gpio_path) {
+            Some(cancelled) => {
+                cancelled.store(true, Ordering::SeqCst);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// `initial_values` is what the lines are opened with on a cache miss — callers that already
+    /// know the values they want (e.g. `write_many`) should pass them here instead of zeros, so
+    /// the lines don't visibly glitch through an all-zero state before `action` sets the real
+    /// values on a freshly opened handle.
+    fn do_with_multi_handle<F, O, E>(
+        &self,
+        gpio_path: GpioMultiPath,
+        flags: LineRequestFlags,
+        initial_values: &[u8],
+        action: F,
+    ) -> AppResult<O>
+    where
+        F: Fn(&MultiLineHandle) -> Result<O, E>,
+        AppError: From<E>,
+        E: Display,
+    {
+        debug!("Trying to acquire a read lock on multi_pins");
+        let cached_handle = {
+            let pins = self.multi_pins.read().unwrap();
+            match pins.get(&gpio_path) {
+                Some((existing_flags, handle)) if *existing_flags == flags => Some(Arc::clone(handle)),
+                Some(_) => {
+                    debug!("Pre-existing multi-line handle was opened with different flags");
+                    None
+                }
+                None => {
+                    debug!("No pre-existing multi-line handle");
+                    None
+                }
+            }
+        };
+        if let Some(handle) = cached_handle {
+            match action(&handle) {
+                Ok(res) => {
+                    debug!("Action succeeded with pre-existing multi-line handle");
+                    return Ok(res); // Happy path, no write lock
+                }
+                Err(e) => {
+                    debug!(
+                        "Action failed with pre-existing multi-line handle ({}); freeing it",
+                        e
+                    );
+                }
+            }
+        }
+        // slow path, application state is locked
+        let mut pins = self.multi_pins.write().unwrap();
+        // drop the old line handle if it exists
+        pins.remove(&gpio_path);
+        info!("Opening device {}", gpio_path.chip);
+        let device_path = format!("/dev/{}", gpio_path.chip); // Sad path, open a new line handle
+        let mut chip = Chip::new(device_path)?;
+        info!("Getting lines {:?}", gpio_path.pins);
+        let lines = chip.get_lines(&gpio_path.pins)?;
+        info!("Making an {:?} request", flags);
+        let handle = lines.request(flags, initial_values, "http-gpio")?;
+        let arc_handle = Arc::new(handle);
+        debug!("Saving the multi-line handle for later");
+        pins.insert(gpio_path, (flags, Arc::clone(&arc_handle)));
+        // Release the lock
+        drop(pins);
+        debug!("Performing action");
+        let result = action(&arc_handle)?;
+        Ok(result)
+    }
+
+    pub fn read_many(&self, gpio_path: GpioMultiPath) -> AppResult<Vec<u8>> {
+        let zeros = vec![0; gpio_path.pins.len()];
+        self.do_with_multi_handle(gpio_path, LineRequestFlags::INPUT, &zeros, |handle| {
+            handle.get_values()
+        })
+    }
+
+    pub fn write_many(&self, gpio_path: GpioMultiPath, values: Vec<u8>) -> AppResult<()> {
+        if gpio_path.pins.len() != values.len() {
+            return Err(AppError::MismatchedLineCount {
+                lines: gpio_path.pins.len(),
+                values: values.len(),
+            });
+        }
+        self.do_with_multi_handle(gpio_path, LineRequestFlags::OUTPUT, &values, |handle| {
+            handle.set_values(&values)
+        })
+    }
+
+    /// Subscribes to edge transitions on a pin; only one subscriber per pin at a time.
+    pub fn subscribe(
+        self: &Arc<Self>,
+        gpio_path: GpioPath,
+        edge: EdgeFilter,
+    ) -> AppResult<EventSubscription> {
+        {
+            let mut events = self.events.write().unwrap();
+            if events.contains_key(&gpio_path) {
+                return Err(AppError::AlreadySubscribed(gpio_path));
+            }
+            events.insert(gpio_path.clone(), ());
+        }
+        info!("Opening device {} for edge events", gpio_path.chip);
+        let device_path = format!("/dev/{}", gpio_path.chip);
+        let mut chip = match Chip::new(device_path) {
+            Ok(chip) => chip,
+            Err(e) => {
+                self.events.write().unwrap().remove(&gpio_path);
+                return Err(e.into());
+            }
+        };
+        let line = match chip.get_line(gpio_path.pin) {
+            Ok(line) => line,
+            Err(e) => {
+                self.events.write().unwrap().remove(&gpio_path);
+                return Err(e.into());
+            }
+        };
+        info!("Requesting edge events for pin {}", gpio_path.pin);
+        let handle = match line.async_events(LineRequestFlags::INPUT, edge.flags(), "http-gpio") {
+            Ok(handle) => handle,
+            Err(e) => {
+                self.events.write().unwrap().remove(&gpio_path);
+                return Err(e.into());
             }
-            Ok(value)
+        };
+        Ok(EventSubscription {
+            inner: Box::pin(handle),
+            state: Arc::clone(self),
+            path: gpio_path,
         })
     }
 }
@@ -213,3 +635,105 @@ impl GpioPinDescription {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_defaults_to_bare_flags() {
+        let flags = LineConfig::default().apply(LineRequestFlags::OUTPUT);
+        assert_eq!(flags, LineRequestFlags::OUTPUT);
+    }
+
+    #[test]
+    fn apply_sets_active_low() {
+        let config = LineConfig { active_low: true, ..LineConfig::default() };
+        let flags = config.apply(LineRequestFlags::INPUT);
+        assert_eq!(flags, LineRequestFlags::INPUT | LineRequestFlags::ACTIVE_LOW);
+    }
+
+    #[test]
+    fn apply_sets_drive_mode() {
+        let open_drain = LineConfig { drive: Drive::OpenDrain, ..LineConfig::default() };
+        assert_eq!(
+            open_drain.apply(LineRequestFlags::OUTPUT),
+            LineRequestFlags::OUTPUT | LineRequestFlags::OPEN_DRAIN
+        );
+
+        let open_source = LineConfig { drive: Drive::OpenSource, ..LineConfig::default() };
+        assert_eq!(
+            open_source.apply(LineRequestFlags::OUTPUT),
+            LineRequestFlags::OUTPUT | LineRequestFlags::OPEN_SOURCE
+        );
+
+        let push_pull = LineConfig { drive: Drive::PushPull, ..LineConfig::default() };
+        assert_eq!(push_pull.apply(LineRequestFlags::OUTPUT), LineRequestFlags::OUTPUT);
+    }
+
+    #[test]
+    fn apply_sets_bias() {
+        let pull_up = LineConfig { bias: Bias::PullUp, ..LineConfig::default() };
+        assert_eq!(
+            pull_up.apply(LineRequestFlags::INPUT),
+            LineRequestFlags::INPUT | LineRequestFlags::BIAS_PULL_UP
+        );
+
+        let pull_down = LineConfig { bias: Bias::PullDown, ..LineConfig::default() };
+        assert_eq!(
+            pull_down.apply(LineRequestFlags::INPUT),
+            LineRequestFlags::INPUT | LineRequestFlags::BIAS_PULL_DOWN
+        );
+    }
+
+    #[test]
+    fn apply_combines_active_low_drive_and_bias() {
+        let config = LineConfig {
+            active_low: true,
+            drive: Drive::OpenDrain,
+            bias: Bias::PullDown,
+            debounce_us: None,
+        };
+        let flags = config.apply(LineRequestFlags::OUTPUT);
+        assert_eq!(
+            flags,
+            LineRequestFlags::OUTPUT
+                | LineRequestFlags::ACTIVE_LOW
+                | LineRequestFlags::OPEN_DRAIN
+                | LineRequestFlags::BIAS_PULL_DOWN
+        );
+    }
+
+    #[test]
+    fn apply_ignores_debounce_us_but_does_not_fail() {
+        let config = LineConfig { debounce_us: Some(500), ..LineConfig::default() };
+        let flags = config.apply(LineRequestFlags::INPUT);
+        assert_eq!(flags, LineRequestFlags::INPUT);
+    }
+
+    #[test]
+    fn interruptible_sleep_returns_immediately_when_pre_cancelled() {
+        let cancelled = AtomicBool::new(true);
+        let before = std::time::Instant::now();
+        let was_cancelled = State::interruptible_sleep(Duration::from_secs(60), &cancelled);
+        assert!(was_cancelled);
+        assert!(before.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn interruptible_sleep_waits_out_the_full_duration_when_not_cancelled() {
+        let cancelled = AtomicBool::new(false);
+        let duration = Duration::from_millis(50);
+        let before = std::time::Instant::now();
+        let was_cancelled = State::interruptible_sleep(duration, &cancelled);
+        assert!(!was_cancelled);
+        assert!(before.elapsed() >= duration);
+    }
+
+    #[test]
+    fn edge_filter_flags_map_to_the_matching_event_request_flags() {
+        assert_eq!(EdgeFilter::Rising.flags(), EventRequestFlags::RISING_EDGE);
+        assert_eq!(EdgeFilter::Falling.flags(), EventRequestFlags::FALLING_EDGE);
+        assert_eq!(EdgeFilter::Both.flags(), EventRequestFlags::BOTH_EDGES);
+    }
+}
@@ -1,5 +1,6 @@
 use structopt::StructOpt;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 
 /// A program which launches a web server to control a machine's GPIO pins over HTTP
 #[derive(StructOpt, Debug)]
@@ -24,4 +25,10 @@ pub struct CommandLineArguments {
     /// code on any page of example.com.
     #[structopt(short, long)]
     pub allow_origin: Vec<String>,
+
+    /// Path to a JSON config file declaring named pin aliases (and, optionally, the state those
+    /// pins should be set to on startup). The file is a JSON object whose `pins` array holds
+    /// `PinAlias` entries.
+    #[structopt(short, long)]
+    pub config: Option<PathBuf>,
 }
\ No newline at end of file
@@ -0,0 +1,136 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use log::warn;
+use serde::Deserialize;
+
+use crate::application_state::GpioPath;
+
+/// A human-friendly name bound to a concrete `chip`/`pin` target, as declared in a `--config`
+/// file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PinAlias {
+    pub name: String,
+    pub chip: String,
+    pub pin: u32,
+    pub default_value: Option<u8>,
+    pub direction: Option<Direction>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Input,
+    Output,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Config {
+    #[serde(default)]
+    pub pins: Vec<PinAlias>,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Parse(serde_json::Error),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "{}", e),
+            ConfigError::Parse(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let contents = fs::read_to_string(path).map_err(ConfigError::Io)?;
+        let config: Self = serde_json::from_str(&contents).map_err(ConfigError::Parse)?;
+        config.warn_on_duplicate_names();
+        Ok(config)
+    }
+
+    /// `resolve` silently picks whichever alias with this name appears first, so a duplicate
+    /// would quietly shadow a pin without any indication at startup.
+    fn warn_on_duplicate_names(&self) {
+        for name in self.duplicate_names() {
+            warn!("Pin alias name {:?} is configured more than once; only the first entry is reachable", name);
+        }
+    }
+
+    /// Names that appear more than once in `pins`, in declaration order. A name is listed once
+    /// for each entry after its first, so a name used three times is listed twice.
+    fn duplicate_names(&self) -> Vec<&str> {
+        let mut seen = HashSet::with_capacity(self.pins.len());
+        self.pins
+            .iter()
+            .map(|alias| alias.name.as_str())
+            .filter(|name| !seen.insert(*name))
+            .collect()
+    }
+
+    /// Looks up a named alias, returning the `GpioPath` it points to.
+    pub fn resolve(&self, name: &str) -> Option<GpioPath> {
+        self.pins
+            .iter()
+            .find(|alias| alias.name == name)
+            .map(|alias| GpioPath::new(alias.chip.clone(), alias.pin))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alias(name: &str, chip: &str, pin: u32) -> PinAlias {
+        PinAlias { name: name.to_string(), chip: chip.to_string(), pin, default_value: None, direction: None }
+    }
+
+    #[test]
+    fn resolve_finds_configured_alias() {
+        let config = Config { pins: vec![alias("led", "gpiochip0", 17)] };
+        let path = config.resolve("led").unwrap();
+        assert_eq!(path, GpioPath::new("gpiochip0".to_string(), 17));
+    }
+
+    #[test]
+    fn resolve_returns_none_for_unknown_name() {
+        let config = Config { pins: vec![alias("led", "gpiochip0", 17)] };
+        assert!(config.resolve("button").is_none());
+    }
+
+    #[test]
+    fn duplicate_names_empty_when_all_unique() {
+        let config = Config { pins: vec![alias("led", "gpiochip0", 17), alias("button", "gpiochip0", 27)] };
+        assert!(config.duplicate_names().is_empty());
+    }
+
+    #[test]
+    fn duplicate_names_reports_repeated_alias() {
+        let config = Config {
+            pins: vec![
+                alias("led", "gpiochip0", 17),
+                alias("led", "gpiochip0", 27),
+            ],
+        };
+        assert_eq!(config.duplicate_names(), vec!["led"]);
+    }
+
+    #[test]
+    fn resolve_prefers_first_entry_on_duplicate_name() {
+        let config = Config {
+            pins: vec![
+                alias("led", "gpiochip0", 17),
+                alias("led", "gpiochip0", 27),
+            ],
+        };
+        assert_eq!(config.resolve("led").unwrap(), GpioPath::new("gpiochip0".to_string(), 17));
+    }
+}
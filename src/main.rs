@@ -1,20 +1,24 @@
+use std::convert::Infallible;
 use std::sync::Arc;
 
-use log::error;
+use futures::stream::StreamExt;
+use log::{error, info};
 use serde::de::DeserializeOwned;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use structopt::StructOpt;
 use warp::{Filter, Rejection};
 use warp::http::StatusCode;
 use warp::hyper::body::Bytes;
 use warp::reply::{json, with_status};
 
-use application_state::{AppResult, GpioPath, State};
+use application_state::{AppError, AppResult, BlinkSchedule, EdgeFilter, EventSubscription, GpioMultiPath, GpioPath, LineConfig, State};
 use application_state::{list_chips, list_pins, single_pin_description};
 use command_line_arguments::CommandLineArguments;
+use config::{Config, Direction};
 
 mod application_state;
 mod command_line_arguments;
+mod config;
 
 #[tokio::main]
 async fn main() {
@@ -25,17 +29,36 @@ async fn main() {
 
     let cors = warp::cors()
         .allow_origins(opts.allow_origin.iter().map(String::as_str))
-        .allow_methods(["GET", "POST"])
+        .allow_methods(["GET", "POST", "DELETE"])
         .build();
 
+    let config = match &opts.config {
+        Some(path) => Config::load(path).unwrap_or_else(|e| {
+            panic!("Failed to load config file {}: {}", path.display(), e)
+        }),
+        None => Config::default(),
+    };
+
     let shared_pins_state = Arc::new(State::new());
+    apply_startup_defaults(&shared_pins_state, &config);
+    let config = Arc::new(config);
+
     let routes =
         gpio_list()
             .or(gpio_pin_list())
             .or(gpio_pin_description())
+            // Ahead of gpio_get/gpio_post: those take a bare `chip`/`pin`, and a numeric-looking
+            // alias (e.g. "3") would otherwise be accepted as chip="named", pin=3 before ever
+            // reaching Config::resolve.
+            .or(gpio_named_get(shared_pins_state.clone(), config.clone()))
+            .or(gpio_named_post(shared_pins_state.clone(), config.clone()))
             .or(gpio_get(shared_pins_state.clone()))
             .or(gpio_post(shared_pins_state.clone()))
             .or(gpio_blink(shared_pins_state.clone()))
+            .or(gpio_blink_cancel(shared_pins_state.clone()))
+            .or(gpio_events(shared_pins_state.clone()))
+            .or(gpio_values_get(shared_pins_state.clone()))
+            .or(gpio_values_post(shared_pins_state.clone()))
             .with(warp::log("http-gpio"))
             .with(cors);
 
@@ -43,6 +66,26 @@ async fn main() {
 }
 
 type StateRef = Arc<State>;
+type ConfigRef = Arc<Config>;
+
+/// Applies each alias's `default_value`, if any, so pins come up in a known state at startup.
+fn apply_startup_defaults(state: &State, config: &Config) {
+    for alias in &config.pins {
+        let value = match alias.default_value {
+            Some(value) => value,
+            None => continue,
+        };
+        if alias.direction == Some(Direction::Input) {
+            error!("Pin alias {:?} has a default_value but is configured as an input; ignoring it", alias.name);
+            continue;
+        }
+        let gpio_path = GpioPath::new(alias.chip.clone(), alias.pin);
+        info!("Applying startup default for {:?}: {} = {}", alias.name, alias.pin, value);
+        if let Err(e) = state.write(gpio_path, value, LineConfig::default()) {
+            error!("Failed to apply startup default for pin alias {:?}: {}", alias.name, e);
+        }
+    }
+}
 
 fn gpio_list() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
     warp::path!("gpio")
@@ -76,13 +119,22 @@ fn gpio_child_path(
         .and(warp::any().map(move || shared_pins_state.clone()))
 }
 
+#[derive(Deserialize)]
+struct WriteRequest {
+    value: u8,
+    #[serde(default)]
+    config: LineConfig,
+}
+
 fn gpio_post(
     state: StateRef,
 ) -> impl Filter<Extract=impl warp::Reply, Error=warp::Rejection> + Clone {
     gpio_child_path(state, "value")
         .and(warp::post())
         .and(any_json())
-        .map(|gpio_path, state: Arc<State>, body| state.write(gpio_path, body))
+        .map(|gpio_path, state: Arc<State>, body: WriteRequest| {
+            state.write(gpio_path, body.value, body.config)
+        })
         .map(create_http_response)
 }
 
@@ -93,28 +145,181 @@ fn gpio_blink(
         .and(warp::post())
         .and(warp::body::content_length_limit(4096))
         .and(any_json())
-        .map(|gpio_path, state: Arc<State>, body| state.write_schedule(gpio_path, body))
+        .map(|gpio_path, state: Arc<State>, body: BlinkSchedule| state.write_schedule(gpio_path, body))
+        .map(create_accepted_response)
+}
+
+fn gpio_blink_cancel(
+    state: StateRef,
+) -> impl Filter<Extract=impl warp::Reply, Error=warp::Rejection> + Clone {
+    gpio_child_path(state, "blink")
+        .and(warp::delete())
+        .map(|gpio_path, state: Arc<State>| -> AppResult<bool> { Ok(state.cancel_blink(&gpio_path)) })
         .map(create_http_response)
 }
 
+fn create_accepted_response<O: Serialize>(r: AppResult<O>) -> Box<dyn warp::Reply> {
+    match r {
+        Ok(value) => Box::new(with_status(json(&value), StatusCode::ACCEPTED)),
+        Err(e) => {
+            error!("{}", e);
+            Box::new(with_status(e.to_string(), status_code_for(&e)))
+        }
+    }
+}
+
+/// Maps an `AppError` to the HTTP status that best describes its cause, so a bad request doesn't
+/// come back indistinguishable from a real hardware/server fault.
+fn status_code_for(e: &AppError) -> StatusCode {
+    match e {
+        AppError::Gpio(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        AppError::AlreadySubscribed(_) => StatusCode::CONFLICT,
+        AppError::MismatchedLineCount { .. } => StatusCode::BAD_REQUEST,
+        AppError::UnknownAlias(_) => StatusCode::NOT_FOUND,
+    }
+}
+
 fn gpio_get(
     state: StateRef,
 ) -> impl Filter<Extract=impl warp::Reply, Error=warp::Rejection> + Clone {
     gpio_child_path(state, "value")
         .and(warp::get())
-        .map(|gpio_path, state: Arc<State>| state.read(gpio_path))
+        .and(warp::query::<LineConfig>())
+        .map(|gpio_path, state: Arc<State>, config: LineConfig| state.read(gpio_path, config))
+        .map(create_http_response)
+}
+
+#[derive(Deserialize)]
+struct WriteManyRequest {
+    lines: Vec<u32>,
+    values: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+struct ReadManyQuery {
+    #[serde(deserialize_with = "deserialize_comma_separated")]
+    lines: Vec<u32>,
+}
+
+fn deserialize_comma_separated<'de, D>(deserializer: D) -> Result<Vec<u32>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let raw = String::deserialize(deserializer)?;
+    raw.split(',')
+        .map(|part| part.trim().parse().map_err(serde::de::Error::custom))
+        .collect()
+}
+
+fn gpio_values_post(
+    state: StateRef,
+) -> impl Filter<Extract=impl warp::Reply, Error=warp::Rejection> + Clone {
+    warp::path!("gpio" / String / "values")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(4096))
+        .and(any_json())
+        .and(warp::any().map(move || state.clone()))
+        .map(|chip: String, body: WriteManyRequest, state: Arc<State>| {
+            state.write_many(GpioMultiPath::new(chip, body.lines), body.values)
+        })
+        .map(create_http_response)
+}
+
+fn gpio_values_get(
+    state: StateRef,
+) -> impl Filter<Extract=impl warp::Reply, Error=warp::Rejection> + Clone {
+    warp::path!("gpio" / String / "values")
+        .and(warp::get())
+        .and(warp::query::<ReadManyQuery>())
+        .and(warp::any().map(move || state.clone()))
+        .map(|chip: String, query: ReadManyQuery, state: Arc<State>| {
+            state.read_many(GpioMultiPath::new(chip, query.lines))
+        })
+        .map(create_http_response)
+}
+
+fn gpio_named_get(
+    state: StateRef,
+    config: ConfigRef,
+) -> impl Filter<Extract=impl warp::Reply, Error=warp::Rejection> + Clone {
+    warp::path!("gpio" / "named" / String / "value")
+        .and(warp::get())
+        .and(warp::query::<LineConfig>())
+        .and(warp::any().map(move || state.clone()))
+        .and(warp::any().map(move || config.clone()))
+        .map(|alias: String, line_config: LineConfig, state: Arc<State>, config: Arc<Config>| {
+            match config.resolve(&alias) {
+                Some(gpio_path) => state.read(gpio_path, line_config),
+                None => Err(AppError::UnknownAlias(alias)),
+            }
+        })
+        .map(create_http_response)
+}
+
+fn gpio_named_post(
+    state: StateRef,
+    config: ConfigRef,
+) -> impl Filter<Extract=impl warp::Reply, Error=warp::Rejection> + Clone {
+    warp::path!("gpio" / "named" / String / "value")
+        .and(warp::post())
+        .and(any_json())
+        .and(warp::any().map(move || state.clone()))
+        .and(warp::any().map(move || config.clone()))
+        .map(|alias: String, body: WriteRequest, state: Arc<State>, config: Arc<Config>| {
+            match config.resolve(&alias) {
+                Some(gpio_path) => state.write(gpio_path, body.value, body.config),
+                None => Err(AppError::UnknownAlias(alias)),
+            }
+        })
         .map(create_http_response)
 }
 
+#[derive(Deserialize)]
+struct EventsQuery {
+    edge: Option<EdgeFilter>,
+}
+
+fn gpio_events(
+    state: StateRef,
+) -> impl Filter<Extract=impl warp::Reply, Error=warp::Rejection> + Clone {
+    warp::path!("gpio" / String / u32 / "events")
+        .and(warp::get())
+        .map(GpioPath::new)
+        .and(warp::query::<EventsQuery>())
+        .and(warp::any().map(move || state.clone()))
+        .map(|gpio_path, query: EventsQuery, state: Arc<State>| {
+            state.subscribe(gpio_path, query.edge.unwrap_or_default())
+        })
+        .map(create_sse_response)
+}
+
+fn create_sse_response(r: AppResult<EventSubscription>) -> Box<dyn warp::Reply> {
+    match r {
+        Ok(events) => {
+            let events = events.map(|event| -> Result<warp::sse::Event, Infallible> {
+                match event {
+                    Ok(event) => Ok(warp::sse::Event::default().json_data(event).unwrap()),
+                    Err(e) => {
+                        error!("{}", e);
+                        Ok(warp::sse::Event::default().event("error").data(e.to_string()))
+                    }
+                }
+            });
+            Box::new(warp::sse::reply(warp::sse::keep_alive().stream(events)))
+        }
+        Err(e) => {
+            error!("{}", e);
+            Box::new(with_status(e.to_string(), status_code_for(&e)))
+        }
+    }
+}
+
 fn create_http_response<O: Serialize>(r: AppResult<O>) -> Box<dyn warp::Reply> {
     match r {
         Ok(value) => Box::new(json(&value)),
         Err(e) => {
             error!("{}", e);
-            Box::new(with_status(
-                e.to_string(),
-                StatusCode::INTERNAL_SERVER_ERROR,
-            ))
+            Box::new(with_status(e.to_string(), status_code_for(&e)))
         }
     }
 }
@@ -127,4 +332,48 @@ pub fn any_json<T: DeserializeOwned + Send>() -> impl Filter<Extract=(T, ), Erro
                 warp::reject::reject()
             })
         })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_lines(raw: &str) -> Result<Vec<u32>, serde_json::Error> {
+        serde_json::from_value::<ReadManyQuery>(serde_json::json!({ "lines": raw }))
+            .map(|query| query.lines)
+    }
+
+    #[test]
+    fn comma_separated_parses_simple_list() {
+        assert_eq!(parse_lines("1,2,3").unwrap(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn comma_separated_trims_whitespace() {
+        assert_eq!(parse_lines(" 1 , 2 ").unwrap(), vec![1, 2]);
+    }
+
+    #[test]
+    fn comma_separated_rejects_empty_segment() {
+        assert!(parse_lines("1,,3").is_err());
+    }
+
+    #[test]
+    fn comma_separated_rejects_non_numeric() {
+        assert!(parse_lines("1,abc").is_err());
+    }
+
+    #[test]
+    fn write_many_rejects_mismatched_line_count() {
+        let state = State::new();
+        let path = GpioMultiPath::new("gpiochip0".to_string(), vec![1, 2]);
+        let err = state.write_many(path, vec![0, 1, 1]).unwrap_err();
+        match err {
+            AppError::MismatchedLineCount { lines, values } => {
+                assert_eq!(lines, 2);
+                assert_eq!(values, 3);
+            }
+            other => panic!("expected MismatchedLineCount, got {:?}", other),
+        }
+    }
 }
\ No newline at end of file